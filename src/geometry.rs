@@ -0,0 +1,98 @@
+use crate::prelude::*;
+use crate::PbrMaterial;
+use std::rc::Rc;
+
+mod bvh;
+pub use bvh::*;
+
+///
+/// A CPU-side version of a triangle mesh, containing the connectivity information (indices) and
+/// per-vertex attributes (positions, normals, uvs, colors, ..).
+///
+#[derive(Clone, Debug, Default)]
+pub struct TriMesh {
+    /// Name.
+    pub name: String,
+    /// The name of the material applied to this mesh, if any. Should match the name of a
+    /// material in [crate::Model::materials] or [crate::Scene::materials].
+    pub material_name: Option<String>,
+    /// The material applied to this mesh, if any.
+    pub material: Option<Rc<PbrMaterial>>,
+    /// The positions of the vertices.
+    pub positions: Positions,
+    /// The indices into the [Self::positions] (and other per-vertex attributes) describing the
+    /// triangles of the mesh, three indices per triangle. `None` means the vertices should be
+    /// used directly, three at a time.
+    pub indices: Option<Vec<u32>>,
+}
+
+impl TriMesh {
+    ///
+    /// Returns the positions of the three vertices of the triangle with the given index.
+    ///
+    pub fn triangle(&self, triangle_index: usize) -> [Vec3; 3] {
+        let get = |i: usize| -> Vec3 {
+            let vertex_index = self
+                .indices
+                .as_ref()
+                .map(|indices| indices[i] as usize)
+                .unwrap_or(i);
+            self.positions.into_vec3_at(vertex_index)
+        };
+        [
+            get(triangle_index * 3),
+            get(triangle_index * 3 + 1),
+            get(triangle_index * 3 + 2),
+        ]
+    }
+
+    ///
+    /// Returns the number of triangles in this mesh.
+    ///
+    pub fn triangle_count(&self) -> usize {
+        self.indices
+            .as_ref()
+            .map(|indices| indices.len() / 3)
+            .unwrap_or(self.positions.len() / 3)
+    }
+}
+
+///
+/// The positions of the vertices, either as `f32` or `f64`.
+///
+#[derive(Clone, Debug)]
+pub enum Positions {
+    /// `f32` positions.
+    F32(Vec<Vec3>),
+    /// `f64` positions, useful for large coordinates where `f32` precision is not enough.
+    F64(Vec<cgmath::Vector3<f64>>),
+}
+
+impl Positions {
+    /// The number of positions.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::F32(v) => v.len(),
+            Self::F64(v) => v.len(),
+        }
+    }
+
+    /// Whether there are no positions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the position at the given index as a [Vec3], converting from `f64` if necessary.
+    pub fn into_vec3_at(&self, index: usize) -> Vec3 {
+        match self {
+            Self::F32(v) => v[index],
+            Self::F64(v) => Vec3::new(v[index].x as f32, v[index].y as f32, v[index].z as f32),
+        }
+    }
+}
+
+impl Default for Positions {
+    fn default() -> Self {
+        Self::F32(Vec::new())
+    }
+}
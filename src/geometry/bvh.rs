@@ -0,0 +1,528 @@
+use crate::prelude::*;
+use crate::TriMesh;
+
+///
+/// A ray, defined by an origin and a (not necessarily normalized) direction, used for querying a
+/// [Bvh] for intersections with the underlying mesh.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    /// The origin of the ray.
+    pub origin: Vec3,
+    /// The direction of the ray.
+    pub direction: Vec3,
+}
+
+///
+/// The result of a successful [Bvh::intersect] query.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Hit {
+    /// The index of the triangle that was hit.
+    pub triangle_index: usize,
+    /// The barycentric coordinates of the hit point within the triangle.
+    pub barycentric: Vec3,
+    /// The ray parameter of the hit point, which lies at `origin + t * direction`. This is the
+    /// distance to the hit point only when the ray direction is normalized.
+    pub t: f32,
+}
+
+///
+/// An axis-aligned bounding box, used by [Bvh] to bound groups of triangles.
+///
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    /// The minimum corner of the box.
+    pub min: Vec3,
+    /// The maximum corner of the box.
+    pub max: Vec3,
+}
+
+impl Aabb {
+    ///
+    /// An empty bounding box, ie. one that does not contain any point. Extending it with a
+    /// triangle or another box gives back exactly that triangle/box.
+    ///
+    pub fn empty() -> Self {
+        Self {
+            min: Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    ///
+    /// Grows this bounding box so it also contains the given triangle. Degenerate (zero-area)
+    /// triangles are still valid points to extend by, so this never poisons the box with `NaN`.
+    ///
+    pub fn extend(&mut self, triangle: [Vec3; 3]) {
+        for p in triangle {
+            self.min.x = self.min.x.min(p.x);
+            self.min.y = self.min.y.min(p.y);
+            self.min.z = self.min.z.min(p.z);
+            self.max.x = self.max.x.max(p.x);
+            self.max.y = self.max.y.max(p.y);
+            self.max.z = self.max.z.max(p.z);
+        }
+    }
+
+    ///
+    /// Grows this bounding box so it also contains the given box.
+    ///
+    pub fn extend_aabb(&mut self, other: &Aabb) {
+        self.min.x = self.min.x.min(other.min.x);
+        self.min.y = self.min.y.min(other.min.y);
+        self.min.z = self.min.z.min(other.min.z);
+        self.max.x = self.max.x.max(other.max.x);
+        self.max.y = self.max.y.max(other.max.y);
+        self.max.z = self.max.z.max(other.max.z);
+    }
+
+    /// The center of the bounding box.
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// The extent (size) of the bounding box along each axis.
+    pub fn extent(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// The surface area of the bounding box, used by the SAH cost heuristic.
+    pub fn area(&self) -> f32 {
+        let e = self.extent();
+        if e.x < 0.0 || e.y < 0.0 || e.z < 0.0 {
+            0.0
+        } else {
+            2.0 * (e.x * e.y + e.y * e.z + e.z * e.x)
+        }
+    }
+
+    /// The index of the longest axis, `0` for x, `1` for y and `2` for z.
+    pub fn longest_axis(&self) -> usize {
+        let e = self.extent();
+        if e.x >= e.y && e.x >= e.z {
+            0
+        } else if e.y >= e.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    fn axis(&self, v: Vec3, axis: usize) -> f32 {
+        match axis {
+            0 => v.x,
+            1 => v.y,
+            _ => v.z,
+        }
+    }
+
+    ///
+    /// Slab-test intersection with the given ray, returning the entry/exit distances `(tmin,
+    /// tmax)` along the ray if it intersects the box, restricted to the given `[tmin, tmax]`
+    /// range.
+    ///
+    fn intersect(&self, ray: &Ray, mut tmin: f32, mut tmax: f32) -> Option<(f32, f32)> {
+        for axis in 0..3 {
+            let origin = self.axis(ray.origin, axis);
+            let direction = self.axis(ray.direction, axis);
+            let min = self.axis(self.min, axis);
+            let max = self.axis(self.max, axis);
+            if direction.abs() < 1e-12 {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+            let inv_d = 1.0 / direction;
+            let mut t0 = (min - origin) * inv_d;
+            let mut t1 = (max - origin) * inv_d;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            tmin = tmin.max(t0);
+            tmax = tmax.min(t1);
+            if tmax < tmin {
+                return None;
+            }
+        }
+        Some((tmin, tmax))
+    }
+}
+
+/// Number of buckets the SAH splitting heuristic sorts centroids into; the
+/// `SAH_BUCKET_COUNT - 1` boundaries between them are the candidate splits.
+const SAH_BUCKET_COUNT: usize = 12;
+/// A node with at most this many triangles becomes a leaf. A node whose triangle centroids all
+/// coincide also becomes a leaf, whatever its triangle count.
+const MAX_LEAF_TRIANGLES: usize = 4;
+
+enum Node {
+    Leaf {
+        bounds: Aabb,
+        triangle_indices: Vec<usize>,
+    },
+    Inner {
+        bounds: Aabb,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+///
+/// A bounding-volume hierarchy built over the triangles of a [TriMesh], used to accelerate
+/// nearest-hit ray queries such as those needed by a CPU ray tracer.
+///
+pub struct Bvh {
+    root: Option<Node>,
+    triangles: Vec<[Vec3; 3]>,
+}
+
+///
+/// Controls how a [Bvh] chooses where to split a node's triangles.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplitMode {
+    /// Split at the median of the centroids along the longest axis. Fast to build, reasonable
+    /// quality.
+    #[default]
+    Median,
+    /// Evaluate the surface-area heuristic over a handful of candidate bucket boundaries and
+    /// pick the split with the lowest estimated traversal cost. Slower to build, better query
+    /// performance.
+    Sah,
+}
+
+impl Bvh {
+    ///
+    /// Builds a [Bvh] over all triangles of the given [TriMesh], using [SplitMode::Median].
+    ///
+    pub fn new(mesh: &TriMesh) -> Self {
+        Self::new_with_mode(mesh, SplitMode::Median)
+    }
+
+    ///
+    /// Builds a [Bvh] over all triangles of the given [TriMesh], using the given [SplitMode].
+    /// An empty mesh results in an empty [Bvh] whose [Self::intersect] always returns `None`.
+    ///
+    pub fn new_with_mode(mesh: &TriMesh, mode: SplitMode) -> Self {
+        let triangle_count = mesh.triangle_count();
+        let triangles: Vec<[Vec3; 3]> = (0..triangle_count).map(|i| mesh.triangle(i)).collect();
+        let mut indices: Vec<usize> = (0..triangle_count).collect();
+        let root = if indices.is_empty() {
+            None
+        } else {
+            Some(build_node(&triangles, &mut indices, mode))
+        };
+        Self { root, triangles }
+    }
+
+    ///
+    /// Finds the closest intersection between the given ray and the triangles of this [Bvh],
+    /// using a front-to-back ordered stack traversal of the hierarchy so the first hit found in
+    /// a leaf can tighten `tmax` for every subsequent node.
+    ///
+    pub fn intersect(&self, ray: &Ray) -> Option<Hit> {
+        let root = self.root.as_ref()?;
+        let mut stack = vec![root];
+        let mut closest: Option<Hit> = None;
+        let mut tmax = f32::INFINITY;
+
+        while let Some(node) = stack.pop() {
+            match node {
+                Node::Leaf {
+                    bounds,
+                    triangle_indices,
+                } => {
+                    if bounds.intersect(ray, 1e-4, tmax).is_none() {
+                        continue;
+                    }
+                    for &triangle_index in triangle_indices {
+                        if let Some(hit) =
+                            intersect_triangle(ray, self.triangles[triangle_index], triangle_index)
+                        {
+                            if hit.t < tmax {
+                                tmax = hit.t;
+                                closest = Some(hit);
+                            }
+                        }
+                    }
+                }
+                Node::Inner {
+                    bounds,
+                    left,
+                    right,
+                } => {
+                    if bounds.intersect(ray, 1e-4, tmax).is_none() {
+                        continue;
+                    }
+                    let left_hit = left.bounds().intersect(ray, 1e-4, tmax);
+                    let right_hit = right.bounds().intersect(ray, 1e-4, tmax);
+                    // Push the farther child first so the nearer child is popped (and traversed)
+                    // first, letting its hits shrink `tmax` before the farther child is visited.
+                    match (left_hit, right_hit) {
+                        (Some((lt, _)), Some((rt, _))) if lt <= rt => {
+                            stack.push(right);
+                            stack.push(left);
+                        }
+                        (Some(_), Some(_)) => {
+                            stack.push(left);
+                            stack.push(right);
+                        }
+                        (Some(_), None) => stack.push(left),
+                        (None, Some(_)) => stack.push(right),
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+        closest
+    }
+}
+
+impl Node {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            Node::Leaf { bounds, .. } => bounds,
+            Node::Inner { bounds, .. } => bounds,
+        }
+    }
+}
+
+fn bounds_of(triangles: &[[Vec3; 3]], indices: &[usize]) -> Aabb {
+    let mut bounds = Aabb::empty();
+    for &i in indices {
+        bounds.extend(triangles[i]);
+    }
+    bounds
+}
+
+fn centroid(triangle: [Vec3; 3]) -> Vec3 {
+    (triangle[0] + triangle[1] + triangle[2]) / 3.0
+}
+
+fn axis_value(v: Vec3, axis: usize) -> f32 {
+    match axis {
+        0 => v.x,
+        1 => v.y,
+        _ => v.z,
+    }
+}
+
+fn build_node(triangles: &[[Vec3; 3]], indices: &mut [usize], mode: SplitMode) -> Node {
+    let bounds = bounds_of(triangles, indices);
+
+    if indices.len() <= MAX_LEAF_TRIANGLES {
+        return Node::Leaf {
+            bounds,
+            triangle_indices: indices.to_vec(),
+        };
+    }
+
+    let mut centroid_bounds = Aabb::empty();
+    for &i in indices.iter() {
+        centroid_bounds.extend([centroid(triangles[i]); 3]);
+    }
+    let axis = centroid_bounds.longest_axis();
+
+    // All centroids coincide, eg. a pile of degenerate triangles at the same point: stop
+    // recursing rather than looping forever trying to find a separating split.
+    if centroid_bounds.extent().x <= 0.0
+        && centroid_bounds.extent().y <= 0.0
+        && centroid_bounds.extent().z <= 0.0
+    {
+        return Node::Leaf {
+            bounds,
+            triangle_indices: indices.to_vec(),
+        };
+    }
+
+    let split = match mode {
+        SplitMode::Median => {
+            let mid = indices.len() / 2;
+            indices.select_nth_unstable_by(mid, |&a, &b| {
+                axis_value(centroid(triangles[a]), axis)
+                    .partial_cmp(&axis_value(centroid(triangles[b]), axis))
+                    .unwrap()
+            });
+            mid
+        }
+        SplitMode::Sah => sah_split(triangles, indices, axis, &centroid_bounds).unwrap_or_else(|| {
+            let mid = indices.len() / 2;
+            indices.select_nth_unstable_by(mid, |&a, &b| {
+                axis_value(centroid(triangles[a]), axis)
+                    .partial_cmp(&axis_value(centroid(triangles[b]), axis))
+                    .unwrap()
+            });
+            mid
+        }),
+    };
+
+    let split = split.clamp(1, indices.len() - 1);
+    let (left_indices, right_indices) = indices.split_at_mut(split);
+    let left = build_node(triangles, left_indices, mode);
+    let right = build_node(triangles, right_indices, mode);
+    Node::Inner {
+        bounds,
+        left: Box::new(left),
+        right: Box::new(right),
+    }
+}
+
+///
+/// Evaluates the surface-area heuristic (`cost = area_left * count_left + area_right *
+/// count_right`) over the `SAH_BUCKET_COUNT - 1` boundaries between [SAH_BUCKET_COUNT] buckets
+/// along `axis` and partitions
+/// `indices` at the boundary with the lowest cost, returning the split point.
+///
+fn sah_split(
+    triangles: &[[Vec3; 3]],
+    indices: &mut [usize],
+    axis: usize,
+    centroid_bounds: &Aabb,
+) -> Option<usize> {
+    let extent = axis_value(centroid_bounds.extent(), axis);
+    if extent <= 0.0 {
+        return None;
+    }
+    let min = axis_value(centroid_bounds.min, axis);
+
+    let bucket_of = |i: usize| -> usize {
+        let c = axis_value(centroid(triangles[i]), axis);
+        let b = (((c - min) / extent) * SAH_BUCKET_COUNT as f32) as usize;
+        b.min(SAH_BUCKET_COUNT - 1)
+    };
+
+    indices.sort_unstable_by_key(|&i| bucket_of(i));
+
+    let mut best_cost = f32::INFINITY;
+    let mut best_split = None;
+    for boundary in 1..SAH_BUCKET_COUNT {
+        let split = indices.partition_point(|&i| bucket_of(i) < boundary);
+        if split == 0 || split == indices.len() {
+            continue;
+        }
+        let left_area = bounds_of(triangles, &indices[..split]).area();
+        let right_area = bounds_of(triangles, &indices[split..]).area();
+        let cost = left_area * split as f32 + right_area * (indices.len() - split) as f32;
+        if cost < best_cost {
+            best_cost = cost;
+            best_split = Some(split);
+        }
+    }
+    best_split
+}
+
+///
+/// Möller–Trumbore ray/triangle intersection, returning the hit distance and barycentric
+/// coordinates if the ray hits the front or back face of the triangle. Degenerate (zero-area)
+/// triangles have a near-zero determinant and are correctly reported as not hit.
+///
+fn intersect_triangle(ray: &Ray, triangle: [Vec3; 3], triangle_index: usize) -> Option<Hit> {
+    let edge1 = triangle[1] - triangle[0];
+    let edge2 = triangle[2] - triangle[0];
+    let h = ray.direction.cross(edge2);
+    let det = edge1.dot(h);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = ray.origin - triangle[0];
+    let u = s.dot(h) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(q) * inv_det;
+    if t <= 1e-6 {
+        return None;
+    }
+    Some(Hit {
+        triangle_index,
+        barycentric: Vec3::new(1.0 - u - v, u, v),
+        t,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn triangle_mesh() -> TriMesh {
+        TriMesh {
+            positions: Positions::F32(vec![
+                Vec3::new(-1.0, -1.0, 0.0),
+                Vec3::new(1.0, -1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_mesh_has_no_hits() {
+        let mesh = TriMesh::default();
+        let bvh = Bvh::new(&mesh);
+        let ray = Ray {
+            origin: Vec3::new(0.0, 0.0, -5.0),
+            direction: Vec3::new(0.0, 0.0, 1.0),
+        };
+        assert!(bvh.intersect(&ray).is_none());
+    }
+
+    #[test]
+    fn ray_hits_single_triangle() {
+        let bvh = Bvh::new(&triangle_mesh());
+        let ray = Ray {
+            origin: Vec3::new(0.0, 0.0, -5.0),
+            direction: Vec3::new(0.0, 0.0, 1.0),
+        };
+        let hit = bvh.intersect(&ray).expect("should hit the triangle");
+        assert_eq!(hit.triangle_index, 0);
+        assert!((hit.t - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn ray_misses_triangle() {
+        let bvh = Bvh::new(&triangle_mesh());
+        let ray = Ray {
+            origin: Vec3::new(10.0, 10.0, -5.0),
+            direction: Vec3::new(0.0, 0.0, 1.0),
+        };
+        assert!(bvh.intersect(&ray).is_none());
+    }
+
+    #[test]
+    fn degenerate_triangle_does_not_poison_bounds() {
+        let mesh = TriMesh {
+            positions: Positions::F32(vec![
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 0.0),
+            ]),
+            ..Default::default()
+        };
+        let bvh = Bvh::new(&mesh);
+        let ray = Ray {
+            origin: Vec3::new(0.0, 0.0, -5.0),
+            direction: Vec3::new(0.0, 0.0, 1.0),
+        };
+        // A degenerate triangle has zero area and should not be reported as hit, nor should it
+        // crash the traversal with a `NaN`-poisoned bounding box.
+        assert!(bvh.intersect(&ray).is_none());
+    }
+
+    #[test]
+    fn sah_mode_still_finds_hit() {
+        let bvh = Bvh::new_with_mode(&triangle_mesh(), SplitMode::Sah);
+        let ray = Ray {
+            origin: Vec3::new(0.0, 0.0, -5.0),
+            direction: Vec3::new(0.0, 0.0, 1.0),
+        };
+        assert!(bvh.intersect(&ray).is_some());
+    }
+}
@@ -0,0 +1,99 @@
+//!
+//! Content-based format detection, used as a fallback when a loaded asset has no usable file
+//! extension to dispatch on (eg. a `data:` URL, an HTTP response, or an in-memory byte buffer).
+//!
+
+///
+/// Inspects the leading bytes of `bytes` and returns the file extension of the format it looks
+/// like, or `None` if the format could not be determined. This mirrors the extensions used
+/// elsewhere in this crate to select a parser (eg. `"glb"`, `"png"`), so the result can be used
+/// directly wherever an extension would otherwise be read from a path.
+///
+pub fn detect_format(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.len() >= 4 && &bytes[0..4] == b"glTF" {
+        return Some("glb");
+    }
+    if bytes.len() >= 8 && &bytes[0..8] == [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A] {
+        return Some("png");
+    }
+    if bytes.len() >= 3 && &bytes[0..3] == [0xFF, 0xD8, 0xFF] {
+        return Some("jpeg");
+    }
+    if bytes.len() >= 12 && &bytes[0..12] == KTX2_MAGIC {
+        return Some("ktx2");
+    }
+    if bytes.len() >= 12 && &bytes[0..12] == KTX1_MAGIC {
+        return Some("ktx");
+    }
+
+    if let Some(text) = std::str::from_utf8(&bytes[..bytes.len().min(256)]).ok() {
+        let trimmed = text.trim_start();
+        if trimmed.starts_with('{') && trimmed.contains("\"asset\"") {
+            return Some("gltf");
+        }
+        if trimmed.lines().any(|l| {
+            let l = l.trim_start();
+            l.starts_with("v ") || l.starts_with("vn ") || l.starts_with("f ") || l == "v"
+        }) {
+            return Some("obj");
+        }
+        if trimmed.starts_with("# .PCD") || trimmed.contains("\nDATA ") {
+            return Some("pcd");
+        }
+    }
+
+    None
+}
+
+const KTX1_MAGIC: [u8; 12] = [
+    0xAB, b'K', b'T', b'X', b' ', b'1', b'1', 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const KTX2_MAGIC: [u8; 12] = [
+    0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detects_glb() {
+        let mut bytes = b"glTF".to_vec();
+        bytes.extend_from_slice(&[2, 0, 0, 0]);
+        assert_eq!(detect_format(&bytes), Some("glb"));
+    }
+
+    #[test]
+    fn detects_png() {
+        let bytes = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0];
+        assert_eq!(detect_format(&bytes), Some("png"));
+    }
+
+    #[test]
+    fn detects_jpeg() {
+        let bytes = [0xFF, 0xD8, 0xFF, 0xE0];
+        assert_eq!(detect_format(&bytes), Some("jpeg"));
+    }
+
+    #[test]
+    fn detects_ascii_gltf() {
+        let bytes = br#"{"asset": {"version": "2.0"}}"#;
+        assert_eq!(detect_format(bytes), Some("gltf"));
+    }
+
+    #[test]
+    fn detects_obj() {
+        let bytes = b"# exported\nv 0.0 0.0 0.0\nf 1 2 3\n";
+        assert_eq!(detect_format(bytes), Some("obj"));
+    }
+
+    #[test]
+    fn unknown_format_returns_none() {
+        assert_eq!(detect_format(b"not a known format"), None);
+    }
+
+    #[test]
+    fn empty_bytes_returns_none() {
+        assert_eq!(detect_format(&[]), None);
+    }
+}
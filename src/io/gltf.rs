@@ -0,0 +1,225 @@
+//!
+//! Deserializing a glTF (`.gltf`/`.glb`) document into a [Model], including parsing each
+//! material's core metallic-roughness parameters and the extended Disney principled BSDF
+//! parameters ([PbrMaterial::transmission], [PbrMaterial::ior], [PbrMaterial::sheen],
+//! [PbrMaterial::sheen_tint], [PbrMaterial::clearcoat] and [PbrMaterial::clearcoat_gloss]).
+//!
+
+#![cfg(feature = "gltf")]
+
+use crate::io::RawAssets;
+use crate::prelude::*;
+use crate::{Model, PbrMaterial, Positions, TriMesh};
+use std::path::Path;
+use std::rc::Rc;
+
+///
+/// Deserializes the glTF document at `path` (`.gltf` or `.glb`) into a [Model]. External buffers
+/// referenced by a `.gltf` file must already be loaded into `raw_assets` alongside the document
+/// itself; a `.glb` carries its buffer inline and needs nothing extra.
+///
+pub fn deserialize_gltf(raw_assets: &mut RawAssets, path: impl AsRef<Path>) -> crate::Result<Model> {
+    let path = path.as_ref();
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let bytes = raw_assets.get(path)?.to_vec();
+
+    let ::gltf::Gltf { document, blob } = ::gltf::Gltf::from_slice(&bytes)?;
+
+    let buffers = document
+        .buffers()
+        .map(|buffer| match buffer.source() {
+            ::gltf::buffer::Source::Bin => blob.clone().ok_or(crate::Error::GltfMissingData),
+            ::gltf::buffer::Source::Uri(uri) => raw_assets.get(dir.join(uri)).map(|b| b.to_vec()),
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    let materials: Vec<Rc<PbrMaterial>> = document
+        .materials()
+        .map(|m| Rc::new(parse_material(&m)))
+        .collect();
+
+    let mut geometries = Vec::new();
+    for mesh in document.meshes() {
+        for primitive in mesh.primitives() {
+            let reader =
+                primitive.reader(|buffer| buffers.get(buffer.index()).map(|b| b.as_slice()));
+            let positions: Vec<Vec3> = reader
+                .read_positions()
+                .ok_or(crate::Error::GltfCorruptData)?
+                .map(|p| Vec3::new(p[0], p[1], p[2]))
+                .collect();
+            let indices = reader
+                .read_indices()
+                .map(|indices| indices.into_u32().collect::<Vec<_>>());
+            let material_index = primitive.material().index();
+
+            geometries.push(TriMesh {
+                name: mesh.name().unwrap_or("mesh").to_owned(),
+                material_name: primitive.material().name().map(|s| s.to_owned()),
+                material: material_index.and_then(|i| materials.get(i)).cloned(),
+                positions: Positions::F32(positions),
+                indices,
+            });
+        }
+    }
+
+    Ok(Model {
+        name: path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("model")
+            .to_owned(),
+        geometries,
+    })
+}
+
+///
+/// Deserializes a glTF material into a [PbrMaterial], reading both the core metallic-roughness
+/// parameters and the extended Disney principled BSDF parameters (see [parse_extended_material]).
+/// Called by [deserialize_gltf] for each material in the document.
+///
+pub(crate) fn parse_material(gltf_material: &::gltf::Material) -> PbrMaterial {
+    let pbr = gltf_material.pbr_metallic_roughness();
+    let mut material = PbrMaterial {
+        name: gltf_material.name().unwrap_or("material").to_owned(),
+        albedo: Color::from_rgba_slice(&pbr.base_color_factor()),
+        metallic: pbr.metallic_factor(),
+        roughness: pbr.roughness_factor(),
+        emissive: Color::from_rgb_slice(&gltf_material.emissive_factor()),
+        ..Default::default()
+    };
+    parse_extended_material(gltf_material, &mut material);
+    material
+}
+
+///
+/// Fills in the extended Disney principled BSDF parameters on the given [PbrMaterial] by reading
+/// the `KHR_materials_transmission`, `KHR_materials_ior`, `KHR_materials_sheen`,
+/// `KHR_materials_clearcoat` and `KHR_materials_specular` extensions of the given glTF material,
+/// if present. Materials that do not use an extension keep the corresponding [PbrMaterial]
+/// default. Called by [parse_material].
+///
+pub(crate) fn parse_extended_material(gltf_material: &::gltf::Material, material: &mut PbrMaterial) {
+    apply_extensions(material, &GltfMaterialExtensions::from(gltf_material));
+}
+
+///
+/// The subset of the `KHR_materials_*` extension values relevant to [PbrMaterial], extracted from
+/// a glTF material. Kept separate from [apply_extensions] so the mapping onto [PbrMaterial] can
+/// be unit tested without constructing a real `::gltf::Material`.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct GltfMaterialExtensions {
+    transmission: Option<f32>,
+    ior: Option<f32>,
+    sheen_roughness: Option<f32>,
+    sheen_color: Option<[f32; 3]>,
+    clearcoat: Option<f32>,
+    clearcoat_roughness: Option<f32>,
+    specular_color: Option<[f32; 3]>,
+}
+
+impl From<&::gltf::Material<'_>> for GltfMaterialExtensions {
+    fn from(gltf_material: &::gltf::Material) -> Self {
+        Self {
+            transmission: gltf_material.transmission().map(|t| t.transmission_factor()),
+            ior: gltf_material.ior(),
+            sheen_roughness: gltf_material.sheen().map(|s| s.sheen_roughness_factor()),
+            sheen_color: gltf_material.sheen().map(|s| s.sheen_color_factor()),
+            clearcoat: gltf_material.clearcoat().map(|c| c.clearcoat_factor()),
+            clearcoat_roughness: gltf_material
+                .clearcoat()
+                .map(|c| c.clearcoat_roughness_factor()),
+            // `specular_factor` is an intensity multiplier (glTF default `1.0`), not a tint;
+            // `specular_color_factor` is the chromatic value that maps onto `specular_tint`, the
+            // same way `sheen_color_factor` maps onto `sheen_tint` above.
+            specular_color: gltf_material.specular().map(|s| s.specular_color_factor()),
+        }
+    }
+}
+
+///
+/// Applies the extracted `KHR_materials_*` extension values onto the given [PbrMaterial], leaving
+/// the corresponding field at its [PbrMaterial] default for every extension that was absent.
+///
+fn apply_extensions(material: &mut PbrMaterial, ext: &GltfMaterialExtensions) {
+    if let Some(transmission) = ext.transmission {
+        material.transmission = transmission;
+    }
+    if let Some(ior) = ext.ior {
+        material.ior = ior;
+    }
+    if let Some(roughness) = ext.sheen_roughness {
+        material.sheen = roughness;
+    }
+    if let Some([r, g, b]) = ext.sheen_color {
+        material.sheen_tint = (r + g + b) / 3.0;
+    }
+    if let Some(clearcoat) = ext.clearcoat {
+        material.clearcoat = clearcoat;
+    }
+    if let Some(roughness) = ext.clearcoat_roughness {
+        material.clearcoat_gloss = 1.0 - roughness;
+    }
+    if let Some([r, g, b]) = ext.specular_color {
+        material.specular_tint = (r + g + b) / 3.0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn apply_extensions_fills_in_present_values() {
+        let mut material = PbrMaterial::default();
+        let ext = GltfMaterialExtensions {
+            transmission: Some(0.9),
+            ior: Some(1.45),
+            sheen_roughness: Some(0.3),
+            sheen_color: Some([0.2, 0.4, 0.6]),
+            clearcoat: Some(0.8),
+            clearcoat_roughness: Some(0.25),
+            specular_color: Some([0.2, 0.6, 1.0]),
+        };
+        apply_extensions(&mut material, &ext);
+
+        assert_eq!(material.transmission, 0.9);
+        assert_eq!(material.ior, 1.45);
+        assert_eq!(material.sheen, 0.3);
+        assert!((material.sheen_tint - 0.4).abs() < 1e-5);
+        assert_eq!(material.clearcoat, 0.8);
+        assert!((material.clearcoat_gloss - 0.75).abs() < 1e-5);
+        assert!((material.specular_tint - 0.6).abs() < 1e-5);
+    }
+
+    #[test]
+    fn apply_extensions_does_not_confuse_specular_intensity_with_tint() {
+        // `specular_factor` (an intensity multiplier, default `1.0`) must never feed
+        // `specular_tint`; only the achromatic-by-default `specular_color_factor` should.
+        let mut material = PbrMaterial::default();
+        apply_extensions(
+            &mut material,
+            &GltfMaterialExtensions {
+                specular_color: Some([0.0, 0.0, 0.0]),
+                ..Default::default()
+            },
+        );
+        assert_eq!(material.specular_tint, 0.0);
+    }
+
+    #[test]
+    fn apply_extensions_keeps_defaults_when_absent() {
+        let mut material = PbrMaterial::default();
+        let defaults = material.clone();
+        apply_extensions(&mut material, &GltfMaterialExtensions::default());
+
+        assert_eq!(material.transmission, defaults.transmission);
+        assert_eq!(material.ior, defaults.ior);
+        assert_eq!(material.sheen, defaults.sheen);
+        assert_eq!(material.sheen_tint, defaults.sheen_tint);
+        assert_eq!(material.clearcoat, defaults.clearcoat);
+        assert_eq!(material.clearcoat_gloss, defaults.clearcoat_gloss);
+        assert_eq!(material.specular_tint, defaults.specular_tint);
+    }
+}
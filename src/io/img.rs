@@ -0,0 +1,27 @@
+//!
+//! Deserializing image bytes (`.png`, `.jpg`, ..) into a [Texture2D].
+//!
+
+#![cfg(feature = "image")]
+
+use crate::texture::{Texture2D, TextureData};
+use crate::Result;
+
+///
+/// Deserializes the given image bytes (eg. the content of a `.png` or `.jpg` file) into a
+/// [Texture2D].
+///
+pub(crate) fn deserialize_img(name: &str, bytes: &[u8]) -> Result<Texture2D> {
+    let img = image::load_from_memory(bytes)?.into_rgba8();
+    let (width, height) = img.dimensions();
+    let data = img
+        .pixels()
+        .map(|p| [p[0], p[1], p[2], p[3]])
+        .collect::<Vec<_>>();
+    Ok(Texture2D {
+        name: name.to_owned(),
+        data: TextureData::RgbaU8(data),
+        width,
+        height,
+    })
+}
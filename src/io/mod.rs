@@ -0,0 +1,154 @@
+//!
+//! Contain functionality to load any type of asset runtime as well as parsers for different
+//! 3D formats that converts the raw bytes to the assets in the root module.
+//!
+
+mod gltf;
+pub use gltf::*;
+
+mod format;
+pub use format::*;
+
+mod obj;
+pub use obj::*;
+
+pub(crate) mod img;
+
+use crate::{Model, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+///
+/// The raw bytes of a set of assets, added with [RawAssets::insert] and read with [RawAssets::get].
+/// This is the intermediate format between raw bytes and the deserialized assets in the root
+/// module, allowing eg. a `.gltf` file and its binary buffers and textures to be loaded together
+/// and then deserialized into a [crate::Model] or a [crate::PbrMaterial].
+///
+#[derive(Default, Debug)]
+pub struct RawAssets {
+    raw_assets: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl RawAssets {
+    ///
+    /// Removes and returns the bytes of the asset at the given path.
+    ///
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> Result<Vec<u8>> {
+        self.raw_assets
+            .remove(path.as_ref())
+            .ok_or_else(|| crate::Error::NotLoaded(path.as_ref().to_str().unwrap_or("").to_string()))
+    }
+
+    ///
+    /// Returns a reference to the bytes of the asset at the given path.
+    ///
+    pub fn get(&self, path: impl AsRef<Path>) -> Result<&[u8]> {
+        self.raw_assets
+            .get(path.as_ref())
+            .map(|v| v.as_slice())
+            .ok_or_else(|| crate::Error::NotLoaded(path.as_ref().to_str().unwrap_or("").to_string()))
+    }
+
+    ///
+    /// Inserts the given bytes at the given path.
+    ///
+    pub fn insert(&mut self, path: impl AsRef<Path>, bytes: Vec<u8>) {
+        self.raw_assets
+            .insert(path.as_ref().to_path_buf(), bytes);
+    }
+
+    ///
+    /// Returns the format (as a file extension, eg. `"gltf"` or `"png"`) to use when
+    /// deserializing the asset at the given path. The extension of `path` is used if present,
+    /// otherwise the format is [detected](detect_format) from the content of the asset, which
+    /// allows deserializing assets loaded from a `data:` URL or another source without a useful
+    /// path.
+    ///
+    pub fn format(&self, path: impl AsRef<Path>) -> Option<String> {
+        if let Some(ext) = path
+            .as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .filter(|e| !e.is_empty())
+        {
+            return Some(ext.to_lowercase());
+        }
+        detect_format(self.get(path).ok()?).map(|f| f.to_owned())
+    }
+}
+
+///
+/// Deserializes the asset at the given path into a [Model], picking the parser to use from
+/// [RawAssets::format] (extension if present, otherwise [detect_format] on the content) rather
+/// than requiring the caller to know the format up front. This is what lets a blob loaded from a
+/// `data:` URL or another source without a useful path still be deserialized.
+///
+pub fn deserialize_model(raw_assets: &mut RawAssets, path: impl AsRef<Path>) -> Result<Model> {
+    let path = path.as_ref();
+    let format = raw_assets
+        .format(path)
+        .ok_or_else(|| crate::Error::UnknownFormat(path.to_string_lossy().into_owned()))?;
+    match format.as_str() {
+        "obj" => obj::deserialize_obj(raw_assets, path),
+        #[cfg(feature = "gltf")]
+        "gltf" | "glb" => gltf::deserialize_gltf(raw_assets, path),
+        #[cfg(not(feature = "gltf"))]
+        "gltf" | "glb" => Err(crate::Error::FeatureMissing("gltf".to_owned())),
+        _ => Err(crate::Error::UnsupportedFormat(format)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_prefers_extension() {
+        let mut raw = RawAssets::default();
+        raw.insert("model.gltf", b"not real gltf content".to_vec());
+        assert_eq!(raw.format("model.gltf").as_deref(), Some("gltf"));
+    }
+
+    #[test]
+    fn format_falls_back_to_content_detection() {
+        let mut raw = RawAssets::default();
+        raw.insert("blob", b"glTF\x02\x00\x00\x00".to_vec());
+        assert_eq!(raw.format("blob").as_deref(), Some("glb"));
+    }
+
+    #[test]
+    fn deserialize_model_dispatches_obj_without_an_extension() {
+        let mut raw = RawAssets::default();
+        raw.insert(
+            "blob",
+            b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n".to_vec(),
+        );
+        let model = deserialize_model(&mut raw, "blob").unwrap();
+        assert_eq!(model.geometries.len(), 1);
+    }
+
+    #[test]
+    fn deserialize_model_fails_on_undetectable_format() {
+        let mut raw = RawAssets::default();
+        raw.insert("blob", b"not a known format".to_vec());
+        assert!(matches!(
+            deserialize_model(&mut raw, "blob"),
+            Err(crate::Error::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn deserialize_model_reports_unsupported_format_distinctly_from_missing_feature() {
+        // A recognized-but-non-model format (eg. an image) is not the same failure as a model
+        // format whose cargo feature is disabled, and should not be reported as one.
+        let mut raw = RawAssets::default();
+        raw.insert(
+            "blob.png",
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A].to_vec(),
+        );
+        assert!(matches!(
+            deserialize_model(&mut raw, "blob.png"),
+            Err(crate::Error::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,441 @@
+//!
+//! Parsing of Wavefront `.obj` geometry and its referenced `.mtl` material libraries into a
+//! [Model].
+//!
+
+use crate::io::RawAssets;
+use crate::prelude::*;
+use crate::texture::Texture2DRef;
+use crate::{Model, PbrMaterial, Positions, TriMesh};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+///
+/// Scans the given `.obj` bytes for `mtllib` statements and returns the paths of the `.mtl`
+/// files it references, resolved relative to `obj_path`. These files (and, via
+/// [mtl_dependencies], their textures) have to be inserted into the [RawAssets] before
+/// [deserialize_obj] is called for the materials to be attached; nothing in this module fetches
+/// them.
+///
+pub fn obj_dependencies(obj_path: impl AsRef<Path>, bytes: &[u8]) -> Vec<PathBuf> {
+    let dir = obj_path.as_ref().parent().unwrap_or_else(|| Path::new(""));
+    text_lines(bytes)
+        .filter_map(|line| line.strip_prefix("mtllib "))
+        .flat_map(|rest| rest.split_whitespace())
+        .map(|name| dir.join(name))
+        .collect()
+}
+
+///
+/// Scans the given `.mtl` bytes for `map_Kd`/`map_Bump`/`map_Ks` statements and returns the
+/// paths of the textures it references, resolved relative to `mtl_path`.
+///
+pub fn mtl_dependencies(mtl_path: impl AsRef<Path>, bytes: &[u8]) -> Vec<PathBuf> {
+    let dir = mtl_path.as_ref().parent().unwrap_or_else(|| Path::new(""));
+    text_lines(bytes)
+        .filter_map(|line| {
+            for prefix in ["map_Kd ", "map_Bump ", "map_Ks "] {
+                if let Some(rest) = line.strip_prefix(prefix) {
+                    // A texture map statement can carry option flags before the filename, eg.
+                    // `map_Kd -clamp on file.png`; the filename is always the last token.
+                    return rest.split_whitespace().last();
+                }
+            }
+            None
+        })
+        .map(|name| dir.join(name))
+        .collect()
+}
+
+///
+/// Builds a compacted local vertex buffer containing only the positions referenced by `indices`
+/// (resolved against the full, file-wide `positions` buffer), and remaps `indices` into that
+/// local buffer. Used so each [TriMesh] group only carries the vertices it actually uses instead
+/// of a clone of the whole file's position buffer.
+///
+fn compact(positions: &[Vec3], indices: &[u32]) -> (Vec<Vec3>, Vec<u32>) {
+    let mut remap = HashMap::new();
+    let mut local_positions = Vec::new();
+    let mut local_indices = Vec::with_capacity(indices.len());
+    for &global in indices {
+        let local = *remap.entry(global).or_insert_with(|| {
+            local_positions.push(positions[global as usize]);
+            (local_positions.len() - 1) as u32
+        });
+        local_indices.push(local);
+    }
+    (local_positions, local_indices)
+}
+
+fn text_lines(bytes: &[u8]) -> impl Iterator<Item = &str> {
+    std::str::from_utf8(bytes)
+        .unwrap_or("")
+        .lines()
+        .map(|l| l.trim())
+}
+
+///
+/// Deserializes the `.obj` file at `obj_path` (and the `.mtl` material libraries and textures it
+/// references) into a [Model]. The `.mtl` files and textures must already have been loaded into
+/// `raw_assets`, eg. by fetching the paths returned by [obj_dependencies] and [mtl_dependencies]
+/// alongside the `.obj` file itself.
+///
+pub fn deserialize_obj(raw_assets: &mut RawAssets, obj_path: impl AsRef<Path>) -> crate::Result<Model> {
+    let obj_path = obj_path.as_ref();
+    let bytes = raw_assets.get(obj_path)?.to_vec();
+
+    let mut materials = HashMap::new();
+    for mtl_path in obj_dependencies(obj_path, &bytes) {
+        if let Ok(mtl_bytes) = raw_assets.get(&mtl_path).map(|b| b.to_vec()) {
+            materials.extend(parse_mtl(raw_assets, &mtl_path, &mtl_bytes)?);
+        }
+    }
+
+    let mut geometries = Vec::new();
+    let mut positions = Vec::new();
+    let mut current_name = "default".to_string();
+    let mut current_material: Option<String> = None;
+    let mut current_indices: Vec<u32> = Vec::new();
+
+    let flush = |geometries: &mut Vec<TriMesh>,
+                 name: &str,
+                 material_name: &Option<String>,
+                 materials: &HashMap<String, Rc<PbrMaterial>>,
+                 positions: &[Vec3],
+                 indices: &[u32]| {
+        if indices.is_empty() {
+            return;
+        }
+        // Remap into a per-group compacted vertex buffer instead of cloning the whole
+        // accumulated `positions`, so memory scales with (vertices + indices) rather than
+        // (groups × vertices) for files with many `usemtl`/`o` boundaries.
+        let (local_positions, local_indices) = compact(positions, indices);
+        geometries.push(TriMesh {
+            name: name.to_string(),
+            material_name: material_name.clone(),
+            material: material_name.as_ref().and_then(|n| materials.get(n)).cloned(),
+            positions: Positions::F32(local_positions),
+            indices: Some(local_indices),
+        });
+    };
+
+    for line in text_lines(&bytes) {
+        if let Some(rest) = line.strip_prefix("v ") {
+            let mut it = rest.split_whitespace();
+            let x: f32 = it.next().unwrap_or("0").parse().unwrap_or(0.0);
+            let y: f32 = it.next().unwrap_or("0").parse().unwrap_or(0.0);
+            let z: f32 = it.next().unwrap_or("0").parse().unwrap_or(0.0);
+            positions.push(Vec3::new(x, y, z));
+        } else if let Some(rest) = line.strip_prefix("f ") {
+            // Faces can reference vertex/uv/normal indices as `v`, `v/vt` or `v/vt/vn`; we only
+            // need the vertex index. The index can also be negative, meaning it is relative to
+            // the vertex count so far (`-1` is the most recently defined vertex), a form several
+            // exporters emit.
+            let face_indices: Vec<u32> = rest
+                .split_whitespace()
+                .map(|token| {
+                    let raw: i64 = token.split('/').next().unwrap_or("0").parse().unwrap_or(0);
+                    let one_based = if raw < 0 {
+                        positions.len() as i64 + 1 + raw
+                    } else {
+                        raw
+                    };
+                    (one_based - 1).max(0) as u32
+                })
+                .collect();
+            // Quads and n-gons are not triangles, so fan-triangulate around the face's first
+            // vertex: (v0, v1, v2), (v0, v2, v3), .., rather than pushing every token flat, which
+            // would desync the index stream for every following face in the group.
+            for i in 1..face_indices.len().saturating_sub(1) {
+                current_indices.push(face_indices[0]);
+                current_indices.push(face_indices[i]);
+                current_indices.push(face_indices[i + 1]);
+            }
+        } else if let Some(rest) = line.strip_prefix("usemtl ") {
+            flush(
+                &mut geometries,
+                &current_name,
+                &current_material,
+                &materials,
+                &positions,
+                &current_indices,
+            );
+            current_indices.clear();
+            current_material = Some(rest.trim().to_string());
+        } else if let Some(rest) = line.strip_prefix("o ") {
+            flush(
+                &mut geometries,
+                &current_name,
+                &current_material,
+                &materials,
+                &positions,
+                &current_indices,
+            );
+            current_indices.clear();
+            current_name = rest.trim().to_string();
+        }
+    }
+    flush(
+        &mut geometries,
+        &current_name,
+        &current_material,
+        &materials,
+        &positions,
+        &current_indices,
+    );
+
+    Ok(Model {
+        name: obj_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("model")
+            .to_string(),
+        geometries,
+    })
+}
+
+///
+/// Parses a Wavefront `.mtl` material library into a map from material name to [PbrMaterial],
+/// mapping the classic Phong statements onto the metallic-roughness model:
+/// - `Kd` (diffuse color) becomes [PbrMaterial::albedo]
+/// - `Ke` (emissive color) becomes [PbrMaterial::emissive]
+/// - `Ns` (specular exponent) is converted to [PbrMaterial::roughness] via
+///   `roughness = sqrt(2 / (Ns + 2))`, the roughness that gives a Blinn-Phong lobe of the same
+///   width as the given specular exponent
+/// - `d` (dissolve) or `1.0 - Tr` (transparency) becomes the alpha of [PbrMaterial::albedo]
+/// - `Ni` becomes [PbrMaterial::ior]
+/// - `map_Kd` becomes [PbrMaterial::albedo_texture] and `map_Ks` becomes
+///   [PbrMaterial::metallic_roughness_texture]; `map_Bump` is resolved as a dependency so
+///   loading still succeeds, but is otherwise ignored since normal mapping is not yet modelled
+///   on [PbrMaterial]
+///
+pub fn parse_mtl(
+    raw_assets: &mut RawAssets,
+    mtl_path: impl AsRef<Path>,
+    bytes: &[u8],
+) -> crate::Result<HashMap<String, Rc<PbrMaterial>>> {
+    let dir = mtl_path
+        .as_ref()
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .to_path_buf();
+
+    let mut materials = HashMap::new();
+    let mut name: Option<String> = None;
+    let mut material = PbrMaterial::default();
+
+    macro_rules! finish_current {
+        () => {
+            if let Some(name) = name.take() {
+                materials.insert(name, Rc::new(std::mem::take(&mut material)));
+            }
+        };
+    }
+
+    for line in text_lines(bytes) {
+        if let Some(rest) = line.strip_prefix("newmtl ") {
+            finish_current!();
+            name = Some(rest.trim().to_string());
+            material = PbrMaterial::default();
+        } else if let Some(rest) = line.strip_prefix("Kd ") {
+            let [r, g, b] = parse_rgb(rest);
+            material.albedo = Color::from_rgba_slice(&[r, g, b, material.albedo.a as f32 / 255.0]);
+        } else if let Some(rest) = line.strip_prefix("Ke ") {
+            let [r, g, b] = parse_rgb(rest);
+            material.emissive = Color::from_rgb_slice(&[r, g, b]);
+        } else if let Some(rest) = line.strip_prefix("Ns ") {
+            let ns: f32 = rest.trim().parse().unwrap_or(0.0);
+            material.roughness = (2.0 / (ns + 2.0)).sqrt();
+        } else if let Some(rest) = line.strip_prefix("d ") {
+            let alpha: f32 = rest.trim().parse().unwrap_or(1.0);
+            material.albedo.a = (alpha.clamp(0.0, 1.0) * 255.0) as u8;
+        } else if let Some(rest) = line.strip_prefix("Tr ") {
+            let transparency: f32 = rest.trim().parse().unwrap_or(0.0);
+            material.albedo.a = ((1.0 - transparency).clamp(0.0, 1.0) * 255.0) as u8;
+        } else if let Some(rest) = line.strip_prefix("Ni ") {
+            material.ior = rest.trim().parse().unwrap_or(1.5);
+        } else if let Some(rest) = line.strip_prefix("map_Kd ") {
+            material.albedo_texture = load_texture(raw_assets, &dir, rest);
+        } else if let Some(rest) = line.strip_prefix("map_Ks ") {
+            material.metallic_roughness_texture = load_texture(raw_assets, &dir, rest);
+        } else if line.strip_prefix("map_Bump ").is_some() || line.strip_prefix("bump ").is_some()
+        {
+            // Bump/normal maps are not represented on `PbrMaterial` and are ignored here.
+            // `mtl_dependencies` still reports `map_Bump` files (but not `bump` ones).
+        }
+    }
+    finish_current!();
+
+    Ok(materials)
+}
+
+fn parse_rgb(rest: &str) -> [f32; 3] {
+    let mut it = rest.split_whitespace();
+    let r = it.next().and_then(|v| v.parse().ok()).unwrap_or(1.0);
+    let g = it.next().and_then(|v| v.parse().ok()).unwrap_or(1.0);
+    let b = it.next().and_then(|v| v.parse().ok()).unwrap_or(1.0);
+    [r, g, b]
+}
+
+#[cfg(feature = "image")]
+fn load_texture(raw_assets: &mut RawAssets, dir: &Path, statement: &str) -> Option<Texture2DRef> {
+    // A texture map statement can carry option flags before the filename, eg. `map_Kd -clamp on
+    // file.png`; the filename is always the last whitespace-separated token.
+    let name = statement.split_whitespace().last()?;
+    let path = dir.join(name);
+    let bytes = raw_assets.get(&path).ok()?;
+    let texture = crate::io::img::deserialize_img(name, bytes).ok()?;
+    Some(Texture2DRef {
+        texture: Rc::new(texture),
+        uv_channel: 0,
+    })
+}
+
+#[cfg(not(feature = "image"))]
+fn load_texture(_raw_assets: &mut RawAssets, _dir: &Path, _statement: &str) -> Option<Texture2DRef> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn obj_dependencies_finds_mtllib() {
+        let bytes = b"mtllib materials.mtl\nv 0 0 0\n";
+        let deps = obj_dependencies("models/scene.obj", bytes);
+        assert_eq!(deps, vec![PathBuf::from("models/materials.mtl")]);
+    }
+
+    #[test]
+    fn mtl_dependencies_finds_textures() {
+        let bytes = b"newmtl m\nmap_Kd diffuse.png\nmap_Ks spec.png\n";
+        let deps = mtl_dependencies("models/materials.mtl", bytes);
+        assert_eq!(
+            deps,
+            vec![
+                PathBuf::from("models/diffuse.png"),
+                PathBuf::from("models/spec.png")
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_mtl_maps_phong_statements() {
+        let mut raw_assets = RawAssets::default();
+        let bytes = b"newmtl red\nKd 1.0 0.0 0.0\nNs 100.0\nd 0.5\nNi 1.33\n";
+        let materials = parse_mtl(&mut raw_assets, "scene.mtl", bytes).unwrap();
+        let red = materials.get("red").expect("material should be parsed");
+        assert_eq!(red.albedo.r, 255);
+        assert_eq!(red.albedo.g, 0);
+        assert_eq!(red.albedo.a, 127);
+        assert!((red.ior - 1.33).abs() < 1e-4);
+        assert!((red.roughness - (2.0f32 / 102.0).sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn deserialize_obj_resolves_negative_relative_indices() {
+        let mut raw_assets = RawAssets::default();
+        raw_assets.insert(
+            "scene.obj",
+            b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n".to_vec(),
+        );
+        let model = deserialize_obj(&mut raw_assets, "scene.obj").unwrap();
+        let mesh = &model.geometries[0];
+        assert_eq!(mesh.triangle_count(), 1);
+        assert_eq!(
+            mesh.triangle(0),
+            [
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn deserialize_obj_fan_triangulates_quads_and_keeps_later_faces_in_sync() {
+        let mut raw_assets = RawAssets::default();
+        // A quad followed by a triangle: if the quad's four indices were pushed flat instead of
+        // fan-triangulated, the index stream would desync and the second face would read the
+        // wrong vertices.
+        raw_assets.insert(
+            "scene.obj",
+            b"v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 2 0 0\nv 2 1 0\n\
+f 1 2 3 4\nf 2 5 6\n"
+                .to_vec(),
+        );
+        let model = deserialize_obj(&mut raw_assets, "scene.obj").unwrap();
+        let mesh = &model.geometries[0];
+        assert_eq!(mesh.triangle_count(), 3);
+        assert_eq!(
+            mesh.triangle(0),
+            [
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+            ]
+        );
+        assert_eq!(
+            mesh.triangle(1),
+            [
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ]
+        );
+        assert_eq!(
+            mesh.triangle(2),
+            [
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(2.0, 1.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn compact_only_keeps_referenced_vertices() {
+        let positions = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(9.0, 9.0, 9.0),
+        ];
+        let (local_positions, local_indices) = compact(&positions, &[0, 1, 2]);
+        assert_eq!(local_positions.len(), 3);
+        assert_eq!(local_indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn multiple_material_groups_do_not_duplicate_full_position_buffer() {
+        let mut raw_assets = RawAssets::default();
+        raw_assets.insert(
+            "scene.obj",
+            b"v 0 0 0\nv 1 0 0\nv 0 1 0\nv 2 0 0\nv 2 1 0\nv 2 1 1\n\
+usemtl a\nf 1 2 3\nusemtl b\nf 4 5 6\n"
+                .to_vec(),
+        );
+        let model = deserialize_obj(&mut raw_assets, "scene.obj").unwrap();
+        assert_eq!(model.geometries.len(), 2);
+        for mesh in &model.geometries {
+            assert_eq!(mesh.positions.len(), 3);
+        }
+    }
+
+    #[test]
+    fn deserialize_obj_attaches_material_by_usemtl() {
+        let mut raw_assets = RawAssets::default();
+        raw_assets.insert("scene.mtl", b"newmtl red\nKd 1.0 0.0 0.0\n".to_vec());
+        raw_assets.insert(
+            "scene.obj",
+            b"mtllib scene.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl red\nf 1 2 3\n".to_vec(),
+        );
+        let model = deserialize_obj(&mut raw_assets, "scene.obj").unwrap();
+        assert_eq!(model.geometries.len(), 1);
+        let mesh = &model.geometries[0];
+        assert_eq!(mesh.material_name.as_deref(), Some("red"));
+        assert_eq!(mesh.material.as_ref().unwrap().albedo.r, 255);
+    }
+}
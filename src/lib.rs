@@ -149,6 +149,10 @@ pub enum Error {
     FeatureMissing(String),
     #[error("failed to deserialize the file {0}")]
     FailedDeserialize(String),
+    #[error("could not determine the format of {0}, it has no file extension and its content could not be detected")]
+    UnknownFormat(String),
+    #[error("the format {0} is not a model format that can be deserialized into a Model")]
+    UnsupportedFormat(String),
     #[error("failed to serialize the file {0}")]
     FailedSerialize(String),
 }
@@ -0,0 +1,88 @@
+use crate::prelude::*;
+use crate::texture::*;
+
+///
+/// A physically-based material used for rendering an object, implementing the metallic-roughness
+/// model as well as the extended [Disney principled BSDF](https://disneyanimation.com/publications/physically-based-shading-at-disney/)
+/// parameters needed to render more advanced materials (eg. glass, cloth or clearcoated surfaces)
+/// in a CPU ray tracer.
+///
+#[derive(Clone, Debug)]
+pub struct PbrMaterial {
+    /// Name.
+    pub name: String,
+    /// Base color, also known as albedo, of the material.
+    pub albedo: Color,
+    /// Texture for the base color, also known as albedo, of the material.
+    pub albedo_texture: Option<Texture2DRef>,
+    /// A value in the range `0.0..=1.0` specifying how metallic the material is.
+    pub metallic: f32,
+    /// A value in the range `0.0..=1.0` specifying how rough the material surface is.
+    pub roughness: f32,
+    /// Texture containing the metallic and roughness parameters which are multiplied with the
+    /// [Self::metallic] and [Self::roughness] values for each pixel (metallic in the blue channel
+    /// and roughness in the green channel).
+    pub metallic_roughness_texture: Option<Texture2DRef>,
+    /// Color of light emitted from this material.
+    pub emissive: Color,
+    /// Texture for the color of light emitted from this material.
+    pub emissive_texture: Option<Texture2DRef>,
+    /// A value that is subtracted from 1.0 to give the amount of subsurface scattering used to
+    /// approximate translucent surfaces such as skin or marble. Defaults to `0.0`.
+    pub subsurface: f32,
+    /// Tints the [Self::metallic] specular highlight towards the base color at grazing angles.
+    /// A value in the range `0.0..=1.0`, defaults to `0.0` (no tint, i.e. achromatic specular).
+    pub specular_tint: f32,
+    /// Degree of anisotropy of the specular highlight, stretching the highlight along the
+    /// tangent direction. A value in the range `0.0..=1.0`, defaults to `0.0` (isotropic).
+    pub anisotropic: f32,
+    /// Amount of a grazing-angle component added to simulate the sheen seen on cloth-like
+    /// materials. A value in the range `0.0..=1.0`, defaults to `0.0`.
+    pub sheen: f32,
+    /// Tints the [Self::sheen] component towards the base color. A value in the range
+    /// `0.0..=1.0`, defaults to `0.0` (achromatic sheen).
+    pub sheen_tint: f32,
+    /// Strength of a second, usually glossier, specular lobe on top of the base material used to
+    /// approximate a clear lacquer coating. A value in the range `0.0..=1.0`, defaults to `0.0`.
+    pub clearcoat: f32,
+    /// Glossiness of the [Self::clearcoat] lobe, interpolating between a satin and a gloss
+    /// appearance. A value in the range `0.0..=1.0`, defaults to `0.0`.
+    pub clearcoat_gloss: f32,
+    /// Fraction of light that is transmitted through the material instead of being reflected,
+    /// used to model glass and other transparent, refractive surfaces. A value in the range
+    /// `0.0..=1.0`, defaults to `0.0` (fully opaque).
+    pub transmission: f32,
+    /// Texture modulating [Self::transmission] per pixel (red channel).
+    pub transmission_texture: Option<Texture2DRef>,
+    /// Index of refraction, also known as eta, used together with [Self::transmission] to bend
+    /// rays passing through the material. Defaults to `1.5`, a typical value for glass.
+    pub ior: f32,
+    /// Alpha cutoff value for alpha masked materials.
+    pub alpha_cutout: Option<f32>,
+}
+
+impl Default for PbrMaterial {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            albedo: Color::WHITE,
+            albedo_texture: None,
+            metallic: 0.0,
+            roughness: 1.0,
+            metallic_roughness_texture: None,
+            emissive: Color::BLACK,
+            emissive_texture: None,
+            subsurface: 0.0,
+            specular_tint: 0.0,
+            anisotropic: 0.0,
+            sheen: 0.0,
+            sheen_tint: 0.0,
+            clearcoat: 0.0,
+            clearcoat_gloss: 0.0,
+            transmission: 0.0,
+            transmission_texture: None,
+            ior: 1.5,
+            alpha_cutout: None,
+        }
+    }
+}
@@ -101,6 +101,149 @@ impl Color {
             self.a as f32 / 255.0,
         ]
     }
+
+    ///
+    /// Convert to a slice of linear color values by mapping the red, green and blue component to
+    /// the range `0.0..=1.0` and applying the sRGB electro-optical transfer function, i.e. the
+    /// inverse of the encoding usually applied when a color is stored as 8-bit components.
+    /// Use this instead of [`Color::to_rgb_slice`] when the color is going to be used in a
+    /// lighting calculation that expects linear inputs, for example a CPU ray tracer.
+    ///
+    pub fn to_linear_slice(&self) -> [f32; 3] {
+        let rgb = self.to_rgb_slice();
+        [
+            srgb_to_linear(rgb[0]),
+            srgb_to_linear(rgb[1]),
+            srgb_to_linear(rgb[2]),
+        ]
+    }
+
+    ///
+    /// Convert to a [`Vec4`] of linear color values the same way as [`Color::to_linear_slice`],
+    /// leaving the alpha component unchanged since alpha is not gamma encoded.
+    ///
+    pub fn to_linear_vec4(&self) -> Vec4 {
+        let rgba = self.to_rgba_slice();
+        Vec4::new(
+            srgb_to_linear(rgba[0]),
+            srgb_to_linear(rgba[1]),
+            srgb_to_linear(rgba[2]),
+            rgba[3],
+        )
+    }
+
+    ///
+    /// Creates a new color from four linear color values in the range `0.0..=1.0`, applying the
+    /// sRGB opto-electronic transfer function before quantizing to `u8`. This is the inverse of
+    /// [`Color::to_linear_vec4`]/[`Color::to_linear_slice`] and should be used when constructing a
+    /// [`Color`] from values produced by a lighting calculation.
+    ///
+    pub fn from_linear_rgba_slice(rgba: &[f32; 4]) -> Self {
+        Self {
+            r: (linear_to_srgb(rgba[0]) * 255.0).round() as u8,
+            g: (linear_to_srgb(rgba[1]) * 255.0).round() as u8,
+            b: (linear_to_srgb(rgba[2]) * 255.0).round() as u8,
+            a: (rgba[3] * 255.0).round() as u8,
+        }
+    }
+
+    ///
+    /// Parses a color from a hex string, accepting `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA`
+    /// (the leading `#` is optional and the digits are case-insensitive). The 3/4 digit forms
+    /// duplicate each digit, eg. `#0f3` is the same color as `#00ff33`. Missing alpha defaults to
+    /// fully opaque.
+    ///
+    pub fn from_hex(hex: &str) -> Result<Self, ColorConversionError> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ColorConversionError::InvalidHex);
+        }
+
+        fn digit(s: &str) -> Result<u8, ColorConversionError> {
+            u8::from_str_radix(s, 16).map_err(|_| ColorConversionError::InvalidHex)
+        }
+        fn expand(c: char) -> String {
+            format!("{c}{c}")
+        }
+
+        match hex.len() {
+            3 | 4 => {
+                let mut chars = hex.chars();
+                let r = digit(&expand(chars.next().ok_or(ColorConversionError::InvalidHex)?))?;
+                let g = digit(&expand(chars.next().ok_or(ColorConversionError::InvalidHex)?))?;
+                let b = digit(&expand(chars.next().ok_or(ColorConversionError::InvalidHex)?))?;
+                let a = match chars.next() {
+                    Some(c) => digit(&expand(c))?,
+                    None => 255,
+                };
+                Ok(Color::new(r, g, b, a))
+            }
+            6 | 8 => {
+                let r = digit(&hex[0..2])?;
+                let g = digit(&hex[2..4])?;
+                let b = digit(&hex[4..6])?;
+                let a = if hex.len() == 8 {
+                    digit(&hex[6..8])?
+                } else {
+                    255
+                };
+                Ok(Color::new(r, g, b, a))
+            }
+            _ => Err(ColorConversionError::InvalidHex),
+        }
+    }
+
+    ///
+    /// Converts this color to a `#RRGGBBAA` hex string.
+    ///
+    pub fn to_hex_string(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+    }
+
+    ///
+    /// Creates a new color from a packed `0xRRGGBBAA` value. Unlike the [`usize`] [`TryFrom`]
+    /// conversion, this always uses a fixed 32-bit byte order regardless of the platform's
+    /// `usize` width, and accepts every value including fully-opaque white (`0xFFFFFFFF`).
+    ///
+    pub fn from_rgba_u32(value: u32) -> Self {
+        Self {
+            r: ((value >> 24) & 0xFF) as u8,
+            g: ((value >> 16) & 0xFF) as u8,
+            b: ((value >> 8) & 0xFF) as u8,
+            a: (value & 0xFF) as u8,
+        }
+    }
+
+    ///
+    /// Packs this color into a `0xRRGGBBAA` value. See [`Color::from_rgba_u32`].
+    ///
+    pub fn to_rgba_u32(&self) -> u32 {
+        (self.r as u32) << 24 | (self.g as u32) << 16 | (self.b as u32) << 8 | self.a as u32
+    }
+}
+
+///
+/// Applies the sRGB electro-optical transfer function to a single normalized channel,
+/// converting it from encoded (gamma) space to linear space.
+///
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+///
+/// Applies the sRGB opto-electronic transfer function to a single normalized channel,
+/// converting it from linear space to encoded (gamma) space.
+///
+fn linear_to_srgb(l: f32) -> f32 {
+    if l <= 0.0031308 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    }
 }
 
 impl Default for Color {
@@ -116,6 +259,8 @@ impl Default for Color {
 pub enum ColorConversionError {
     /// Overflow occurren while converting to color
     Overflow,
+    /// The hex string was not a valid `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA` color
+    InvalidHex,
 }
 
 impl TryFrom<usize> for Color {
@@ -150,6 +295,40 @@ impl From<Color> for usize {
 mod test {
     use crate::{Color, ColorConversionError};
 
+    #[test]
+    fn from_hex_accepts_all_forms() {
+        assert_eq!(Color::from_hex("#F00"), Ok(Color::new(255, 0, 0, 255)));
+        assert_eq!(Color::from_hex("0f08"), Ok(Color::new(0, 255, 0, 136)));
+        assert_eq!(Color::from_hex("#0000FF"), Ok(Color::new(0, 0, 255, 255)));
+        assert_eq!(Color::from_hex("ff00ff80"), Ok(Color::new(255, 0, 255, 128)));
+    }
+
+    #[test]
+    fn from_hex_rejects_invalid_input() {
+        assert_eq!(Color::from_hex("#12345"), Err(ColorConversionError::InvalidHex));
+        assert_eq!(Color::from_hex("#gggggg"), Err(ColorConversionError::InvalidHex));
+    }
+
+    #[test]
+    fn from_hex_rejects_non_ascii_without_panicking() {
+        assert_eq!(Color::from_hex("€12345"), Err(ColorConversionError::InvalidHex));
+    }
+
+    #[test]
+    fn to_hex_string_roundtrip() {
+        let color = Color::new(18, 52, 86, 128);
+        assert_eq!(color.to_hex_string(), "#12345680");
+        assert_eq!(Color::from_hex(&color.to_hex_string()), Ok(color));
+    }
+
+    #[test]
+    fn rgba_u32_roundtrip_including_opaque_white() {
+        assert_eq!(Color::from_rgba_u32(0xFFFFFFFF), Color::WHITE);
+        assert_eq!(Color::WHITE.to_rgba_u32(), 0xFFFFFFFF);
+        let color = Color::new(10, 20, 30, 40);
+        assert_eq!(Color::from_rgba_u32(color.to_rgba_u32()), color);
+    }
+
     #[test]
     fn color_roundtrip() {
         fn test_ok(input: Color, expected_output: Color) {
@@ -172,6 +351,36 @@ mod test {
         test_err(Color::new(255, 255, 255, 255));
     }
 
+    #[test]
+    fn linear_roundtrip() {
+        let encoded = Color::new(128, 64, 32, 255);
+        let linear = encoded.to_linear_vec4();
+        let decoded = Color::from_linear_rgba_slice(&[linear.x, linear.y, linear.z, linear.w]);
+        assert_eq!(encoded, decoded);
+    }
+
+    #[test]
+    fn linear_roundtrip_every_channel_value() {
+        // Every encoded value in 0..=255 must round-trip exactly; `linear_to_srgb(1.0) * 255.0`
+        // in particular evaluates to `254.99998` in `f32`, which truncates to 254 without
+        // rounding.
+        for value in 0..=255u8 {
+            let encoded = Color::new(value, value, value, value);
+            let linear = encoded.to_linear_vec4();
+            let decoded = Color::from_linear_rgba_slice(&[linear.x, linear.y, linear.z, linear.w]);
+            assert_eq!(encoded, decoded);
+        }
+    }
+
+    #[test]
+    fn to_linear_matches_known_values() {
+        assert_eq!(Color::new(0, 0, 0, 0).to_linear_slice(), [0.0, 0.0, 0.0]);
+        let white = Color::WHITE.to_linear_slice();
+        for c in white {
+            assert!((c - 1.0).abs() < 1e-5);
+        }
+    }
+
     #[test]
     fn usize_roundtrip() {
         fn test_ok(input: usize, expected_output: usize) {
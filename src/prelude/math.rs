@@ -0,0 +1,18 @@
+//!
+//! Re-export of the linear algebra types used throughout this crate.
+//!
+
+/// A vector with two elements.
+pub type Vec2 = cgmath::Vector2<f32>;
+/// A vector with three elements.
+pub type Vec3 = cgmath::Vector3<f32>;
+/// A vector with four elements.
+pub type Vec4 = cgmath::Vector4<f32>;
+/// A 3x3 matrix.
+pub type Mat3 = cgmath::Matrix3<f32>;
+/// A 4x4 matrix.
+pub type Mat4 = cgmath::Matrix4<f32>;
+/// A quaternion.
+pub type Quat = cgmath::Quaternion<f32>;
+
+pub use cgmath::prelude::*;
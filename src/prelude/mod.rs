@@ -0,0 +1,9 @@
+//!
+//! Common types used throughout this crate, re-exported for convenience.
+//!
+
+mod color;
+pub use color::*;
+
+mod math;
+pub use math::*;
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+use std::rc::Rc;
+
+///
+/// A CPU-side version of a 2D texture, containing the pixel data and how to interpret it.
+///
+#[derive(Clone, Debug, Default)]
+pub struct Texture2D {
+    /// Name of this texture.
+    pub name: String,
+    /// The pixel data for the image.
+    pub data: TextureData,
+    /// The width of the image.
+    pub width: u32,
+    /// The height of the image.
+    pub height: u32,
+}
+
+///
+/// A reference to a [Texture2D] together with the information needed to sample it,
+/// ie. which UV channel to use.
+///
+#[derive(Clone, Debug)]
+pub struct Texture2DRef {
+    /// A reference to the texture.
+    pub texture: Rc<Texture2D>,
+    /// The texture coordinate channel to use when sampling this texture.
+    pub uv_channel: u32,
+}
+
+///
+/// Possible formats for pixel data.
+///
+#[derive(Clone, Debug)]
+pub enum TextureData {
+    /// The pixel data is stored as bytes, one byte per channel.
+    RgbaU8(Vec<[u8; 4]>),
+    /// The pixel data is stored as `f32`, one value per channel.
+    RgbaF32(Vec<[f32; 4]>),
+}
+
+impl Default for TextureData {
+    fn default() -> Self {
+        Self::RgbaU8(Vec::new())
+    }
+}